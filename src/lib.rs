@@ -42,10 +42,19 @@
 //! Hello, world!
 //! Goodnight, sun!
 //! ```
+//!
+//! # `no_std`
+//!
+//! The `std` feature is enabled by default and preserves the crate's
+//! previous behavior. Depend on this crate with `default-features = false`
+//! to build `no_std`. The crate only ever used `std` for re-exports that
+//! now live in `core::ffi` and `core::fmt`, so there is no behavior
+//! difference either way.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::fmt::{self, Display};
-use std::ffi::CStr;
-use std::os::raw::c_char;
+use core::ffi::{c_char, CStr};
+use core::fmt::{self, Display};
 
 /// A reference to a C-compatible string constant.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -54,6 +63,44 @@ pub struct ConstCStr {
 }
 
 impl ConstCStr {
+    /// Creates a constant C string reference from a string slice, validating
+    /// at compile time that it is NUL-terminated and free of interior NUL
+    /// bytes.
+    ///
+    /// Prefer using the `const_cstr!` macro than calling this function directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time, when evaluated in a `const` context) if
+    /// `val` is empty, does not end with a NUL byte, or contains a NUL byte
+    /// before the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zombiezen_const_cstr::ConstCStr;
+    ///
+    /// const S: ConstCStr = ConstCStr::from_str_with_nul("foo\0");
+    /// assert_eq!(S.as_str(), "foo");
+    /// ```
+    #[inline]
+    pub const fn from_str_with_nul(val: &'static str) -> ConstCStr {
+        let bytes = val.as_bytes();
+        assert!(!bytes.is_empty(), "const_cstr: string must not be empty");
+        assert!(
+            bytes[bytes.len() - 1] == 0,
+            "const_cstr: string must end with a NUL byte",
+        );
+        let mut i = 0;
+        while i < bytes.len() - 1 {
+            if bytes[i] == 0 {
+                panic!("const_cstr: interior NUL byte");
+            }
+            i += 1;
+        }
+        ConstCStr { val }
+    }
+
     /// Unsafely creates a constant C string reference from a string slice.
     ///
     /// Prefer using the `const_cstr!` macro than calling this function directly.
@@ -78,14 +125,19 @@ impl ConstCStr {
 
     /// Returns the referenced string without the terminating NUL byte.
     #[inline]
-    pub fn as_str(self) -> &'static str {
-        &self.val[..self.val.len() - 1]
+    pub const fn as_str(self) -> &'static str {
+        // Slicing is not yet stable in const contexts; `val` is already
+        // valid UTF-8 and the NUL terminator is a single ASCII byte, so
+        // splitting the bytes at the last index is always a valid
+        // UTF-8 boundary.
+        let (s, _) = self.val.as_bytes().split_at(self.val.len() - 1);
+        unsafe { core::str::from_utf8_unchecked(s) }
     }
 
     /// Returns the referenced string as a byte slice **without** the
     /// terminating NUL byte.
     #[inline]
-    pub fn as_bytes(self) -> &'static [u8] {
+    pub const fn as_bytes(self) -> &'static [u8] {
         self.as_str().as_bytes()
     }
 
@@ -107,13 +159,35 @@ impl ConstCStr {
 
     /// Returns `&'static CStr` to the referenced string.
     #[inline]
-    pub fn as_cstr(self) -> &'static CStr {
+    pub const fn as_cstr(self) -> &'static CStr {
         unsafe { CStr::from_bytes_with_nul_unchecked(self.as_bytes_with_nul()) }
     }
+
+    /// Returns the number of bytes in the referenced string, **not**
+    /// including the terminating NUL byte.
+    #[inline]
+    pub const fn len(self) -> usize {
+        self.val.len() - 1
+    }
+
+    /// Returns `true` if the referenced string is empty.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// Converts the referenced string to a `&'static str`.
+    ///
+    /// Mirrors [`CStr::to_str`], but since a `ConstCStr` is always valid
+    /// UTF-8, this never fails.
+    #[inline]
+    pub const fn to_str(self) -> Result<&'static str, core::str::Utf8Error> {
+        Ok(self.as_str())
+    }
 }
 
 impl Display for ConstCStr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.as_str().fmt(f)
     }
 }
@@ -168,18 +242,76 @@ impl From<ConstCStr> for &'static CStr {
     }
 }
 
+impl PartialEq<CStr> for ConstCStr {
+    fn eq(&self, other: &CStr) -> bool {
+        self.as_cstr() == other
+    }
+}
+
+impl PartialEq<ConstCStr> for CStr {
+    fn eq(&self, other: &ConstCStr) -> bool {
+        self == other.as_cstr()
+    }
+}
+
+impl PartialEq<&CStr> for ConstCStr {
+    fn eq(&self, other: &&CStr) -> bool {
+        self.as_cstr() == *other
+    }
+}
+
+impl PartialEq<ConstCStr> for &CStr {
+    fn eq(&self, other: &ConstCStr) -> bool {
+        *self == other.as_cstr()
+    }
+}
+
 /// Create a C-compatible constant string by appending a NUL byte to the
 /// passed string.
 ///
-/// See crate root documentation for example usage.
+/// Multiple `&str` expressions may be passed (not just string literals —
+/// any `const`-evaluable `&'static str`, such as another crate's constant),
+/// in which case they are concatenated in order before the NUL byte is
+/// appended.
 ///
-/// # Safety
+/// See crate root documentation for example usage.
 ///
-/// The passed string must not contain any NUL bytes.
+/// The string is validated at compile time: an embedded NUL byte is a
+/// compile error rather than undefined behavior.
 #[macro_export]
 macro_rules! const_cstr {
-    ($strval:expr) => {
-        unsafe { $crate::ConstCStr::from_str_with_nul_unchecked(concat!($strval, "\0")) }
+    ($($part:expr),+ $(,)?) => {
+        const {
+            const __PARTS: &[&str] = &[$($part),+];
+            const __LEN: usize = {
+                let mut n = 1;
+                let mut i = 0;
+                while i < __PARTS.len() {
+                    n += __PARTS[i].len();
+                    i += 1;
+                }
+                n
+            };
+            const __BYTES: [u8; __LEN] = {
+                let mut buf = [0u8; __LEN];
+                let mut pos = 0;
+                let mut i = 0;
+                while i < __PARTS.len() {
+                    let part = __PARTS[i].as_bytes();
+                    let mut j = 0;
+                    while j < part.len() {
+                        buf[pos] = part[j];
+                        pos += 1;
+                        j += 1;
+                    }
+                    i += 1;
+                }
+                buf
+            };
+            $crate::ConstCStr::from_str_with_nul(unsafe {
+                core::str::from_utf8_unchecked(&__BYTES)
+            })
+        }
     };
 }
 
@@ -195,11 +327,74 @@ mod tests {
         assert_eq!(HELLO.as_bytes_with_nul(), b"Hello, World!\0");
         assert_eq!(
             unsafe { CStr::from_ptr(HELLO.as_ptr()) },
-            CStr::from_bytes_with_nul(b"Hello, World!\0").unwrap(),
+            c"Hello, World!",
         );
         assert_eq!(
             HELLO.as_cstr(),
-            CStr::from_bytes_with_nul(b"Hello, World!\0").unwrap(),
+            c"Hello, World!",
         );
     }
+
+    #[test]
+    fn test_concat() {
+        const GREETING: ConstCStr = const_cstr!("Hello, ", "World", "!");
+        assert_eq!(GREETING.as_str(), "Hello, World!");
+    }
+
+    #[test]
+    fn test_concat_non_literal() {
+        const VERSION: &str = "1.2.3";
+        const TAGGED: ConstCStr = const_cstr!("prefix-", VERSION, "-suffix");
+        assert_eq!(TAGGED.as_str(), "prefix-1.2.3-suffix");
+    }
+
+    #[test]
+    fn test_const_accessors() {
+        const HELLO: ConstCStr = const_cstr!("Hello, World!");
+        const HELLO_STR: &str = HELLO.as_str();
+        const HELLO_BYTES: &[u8] = HELLO.as_bytes();
+        const HELLO_CSTR: &CStr = HELLO.as_cstr();
+        assert_eq!(HELLO_STR, "Hello, World!");
+        assert_eq!(HELLO_BYTES, b"Hello, World!");
+        assert_eq!(HELLO_CSTR, c"Hello, World!");
+    }
+
+    #[test]
+    fn test_inspection() {
+        const HELLO: ConstCStr = const_cstr!("Hello, World!");
+        const EMPTY: ConstCStr = const_cstr!("");
+        assert_eq!(HELLO.len(), 13);
+        assert!(!HELLO.is_empty());
+        assert_eq!(HELLO.to_str(), Ok("Hello, World!"));
+        assert_eq!(EMPTY.len(), 0);
+        assert!(EMPTY.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "const_cstr: interior NUL byte")]
+    fn test_interior_nul_panics() {
+        ConstCStr::from_str_with_nul("foo\0bar\0");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_cstr: string must end with a NUL byte")]
+    fn test_missing_trailing_nul_panics() {
+        ConstCStr::from_str_with_nul("foo");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_cstr: string must not be empty")]
+    fn test_empty_string_panics() {
+        ConstCStr::from_str_with_nul("");
+    }
+
+    #[test]
+    fn test_eq_cstr() {
+        const HELLO: ConstCStr = const_cstr!("Hello, World!");
+        let runtime_cstr = c"Hello, World!";
+        assert_eq!(HELLO, *runtime_cstr);
+        assert_eq!(HELLO, runtime_cstr);
+        assert_eq!(*runtime_cstr, HELLO);
+        assert_eq!(runtime_cstr, HELLO);
+    }
 }